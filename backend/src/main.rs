@@ -1,10 +1,14 @@
 use actix_web::{web, App, HttpServer, HttpResponse, Result, middleware::Logger};
 use actix_cors::Cors;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::sync::{Arc, Mutex};
 use std::collections::HashMap;
 use uuid::Uuid;
 
+mod store;
+use store::{FileLotteryStore, LotteryStore};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Lottery {
     pub id: String,
@@ -16,6 +20,63 @@ pub struct Lottery {
     pub winner: Option<String>,
     pub created_at: String,
     pub end_time: Option<String>,
+    /// SHA-256 hex digest of the admin's secret draw seed, fixed before any tickets sell.
+    pub seed_commitment: String,
+    /// The preimage revealed by the admin once the draw runs, so the result can be recomputed.
+    pub revealed_seed: Option<String>,
+    /// Hex digest of the sorted `wallet:tickets` list folded into the draw, for offline replay.
+    pub participant_digest: Option<String>,
+    /// Weighted prize tiers drawn per slot when the winner is picked.
+    pub prize_tiers: Vec<PrizeTier>,
+    /// Number of prize slots rolled per draw; each ensured tier consumes one.
+    pub prize_slots: u32,
+    /// Per-tier awards produced by the most recent draw.
+    pub awards: Vec<Award>,
+    /// Number of distinct wallets drawn without replacement when the winner is picked.
+    pub winners_count: u32,
+    /// Payout in basis points per rank (index 0 = rank 1). `None` splits `prize_pool` equally.
+    pub payout_schedule: Option<Vec<u64>>,
+    /// Winners from the most recent draw, ordered by rank. `winner` mirrors rank 1.
+    pub winners: Vec<WinnerEntry>,
+    /// Share reserved for non-winners, in basis points, of the pool left after the
+    /// winners and tiered awards are paid, not of the full `prize_pool`.
+    pub consolation_bps: u64,
+    /// How the reserved consolation pool is split among non-winners.
+    pub refund_policy: RefundPolicy,
+    /// Consolation refunds from the most recent draw.
+    pub consolation: Vec<ConsolationEntry>,
+    /// When true, the background sweeper draws the winner automatically once `end_time` passes.
+    pub auto_draw: bool,
+    /// Seed kept server-side so the sweeper can reveal it without admin interaction.
+    /// Only set for `auto_draw` lotteries; manual draws never populate this.
+    /// Never serialized out: it would let anyone compute the draw outcome
+    /// ahead of time over the API, defeating the whole commit-reveal scheme.
+    /// Also dropped when persisting to disk, since `FileLotteryStore` writes
+    /// this same `Serialize` impl — an `auto_draw` lottery simply can't be
+    /// auto-drawn again after a restart and falls back to a manual draw.
+    #[serde(skip_serializing, default)]
+    pub auto_draw_seed: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RefundPolicy {
+    None,
+    Proportional,
+    Flat,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsolationEntry {
+    pub wallet: String,
+    pub amount: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WinnerEntry {
+    pub wallet: String,
+    pub rank: u32,
+    pub amount: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,11 +85,41 @@ pub struct Participant {
     pub tickets_bought: u32,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrizeTier {
+    pub name: String,
+    /// Weight used when sampling this tier against other non-ensured tiers.
+    pub ratio: u64,
+    /// Share this tier pays out, in basis points (1/100th of a percent), of the pool
+    /// left after the winners and any earlier slots are paid, not of the full `prize_pool`.
+    pub payout_bps: u64,
+    /// If true, this tier is excluded from the weighted roll and awarded one slot per draw.
+    pub ensured: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Award {
+    pub tier: String,
+    pub wallet: String,
+    pub amount: u64,
+}
+
 #[derive(Deserialize)]
 pub struct CreateLotteryRequest {
     pub admin: String,
     pub ticket_price: u64,
     pub end_time: Option<String>,
+    /// SHA-256 hex digest of a secret seed the admin keeps until the draw.
+    pub seed_commitment: String,
+    pub prize_tiers: Vec<PrizeTier>,
+    pub prize_slots: u32,
+    pub winners_count: u32,
+    pub payout_schedule: Option<Vec<u64>>,
+    pub consolation_bps: u64,
+    pub refund_policy: RefundPolicy,
+    pub auto_draw: bool,
+    /// Required when `auto_draw` is true; must hash to `seed_commitment`.
+    pub auto_draw_seed: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -40,14 +131,73 @@ pub struct BuyTicketRequest {
 #[derive(Deserialize)]
 pub struct PickWinnerRequest {
     pub admin: String,
+    /// Preimage of `seed_commitment`, verified before the draw runs.
+    pub seed: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VerifyResponse {
+    pub lottery_id: String,
+    pub seed_commitment: String,
+    pub revealed_seed: Option<String>,
+    pub participant_digest: Option<String>,
+    pub total_tickets: u32,
+    pub winners: Vec<WinnerEntry>,
+    pub winner: Option<String>,
+    pub consolation: Vec<ConsolationEntry>,
+    pub awards: Vec<Award>,
 }
 
 type LotteryState = Arc<Mutex<HashMap<String, Lottery>>>;
+type LotteryStoreHandle = Arc<dyn LotteryStore>;
 
 async fn create_lottery(
     data: web::Json<CreateLotteryRequest>,
     state: web::Data<LotteryState>,
+    store: web::Data<LotteryStoreHandle>,
 ) -> Result<HttpResponse> {
+    if data.consolation_bps > 10000 {
+        return Ok(HttpResponse::BadRequest().json("consolation_bps must be between 0 and 10000"));
+    }
+    if data.prize_tiers.iter().any(|t| t.payout_bps > 10000) {
+        return Ok(HttpResponse::BadRequest().json("prize tier payout_bps must be between 0 and 10000"));
+    }
+    if let Some(schedule) = &data.payout_schedule {
+        if schedule.iter().any(|bps| *bps > 10000) {
+            return Ok(HttpResponse::BadRequest().json("payout_schedule entries must be between 0 and 10000"));
+        }
+        // Each entry pays out against the same full prize_pool snapshot, so
+        // the aggregate must itself stay within it or winners combined could
+        // be paid more than the pool holds.
+        if schedule.iter().sum::<u64>() > 10000 {
+            return Ok(HttpResponse::BadRequest().json("payout_schedule entries must not sum to more than 10000"));
+        }
+    }
+    // Both bounds guard the draw: `pick_winner` rolls one slot/rank per unit
+    // while holding the global lottery lock, so an unbounded value lets a
+    // single caller freeze every other request (and, for prize_slots, grow
+    // `awards` without limit).
+    if data.prize_slots > 1000 {
+        return Ok(HttpResponse::BadRequest().json("prize_slots cannot exceed 1000"));
+    }
+    if data.winners_count > 1000 {
+        return Ok(HttpResponse::BadRequest().json("winners_count cannot exceed 1000"));
+    }
+
+    if data.auto_draw {
+        match &data.auto_draw_seed {
+            Some(seed) if sha256_hex(seed.as_bytes()) == data.seed_commitment => {}
+            Some(_) => {
+                return Ok(HttpResponse::BadRequest()
+                    .json("auto_draw_seed does not match seed_commitment"));
+            }
+            None => {
+                return Ok(HttpResponse::BadRequest()
+                    .json("auto_draw_seed is required when auto_draw is enabled"));
+            }
+        }
+    }
+
     let lottery_id = Uuid::new_v4().to_string();
     let lottery = Lottery {
         id: lottery_id.clone(),
@@ -59,11 +209,28 @@ async fn create_lottery(
         winner: None,
         created_at: chrono::Utc::now().to_rfc3339(),
         end_time: data.end_time.clone(),
+        seed_commitment: data.seed_commitment.clone(),
+        revealed_seed: None,
+        participant_digest: None,
+        prize_tiers: data.prize_tiers.clone(),
+        prize_slots: data.prize_slots,
+        awards: Vec::new(),
+        winners_count: data.winners_count,
+        payout_schedule: data.payout_schedule.clone(),
+        winners: Vec::new(),
+        consolation_bps: data.consolation_bps,
+        refund_policy: data.refund_policy,
+        consolation: Vec::new(),
+        auto_draw: data.auto_draw,
+        auto_draw_seed: if data.auto_draw { data.auto_draw_seed.clone() } else { None },
     };
 
     let mut lotteries = state.lock().unwrap();
     lotteries.insert(lottery_id.clone(), lottery.clone());
-    
+    if let Err(e) = store.upsert(&lottery) {
+        eprintln!("failed to persist lottery {}: {}", lottery.id, e);
+    }
+
     Ok(HttpResponse::Ok().json(lottery))
 }
 
@@ -71,6 +238,7 @@ async fn buy_ticket(
     lottery_id: web::Path<String>,
     data: web::Json<BuyTicketRequest>,
     state: web::Data<LotteryState>,
+    store: web::Data<LotteryStoreHandle>,
 ) -> Result<HttpResponse> {
     // Validate input
     if data.tickets == 0 {
@@ -89,7 +257,10 @@ async fn buy_ticket(
         if !lottery.is_active {
             return Ok(HttpResponse::BadRequest().json("Lottery is not active"));
         }
-        
+        if lottery.end_time.as_deref().map(end_time_passed).unwrap_or(false) {
+            return Ok(HttpResponse::BadRequest().json("Ticket sales have closed for this lottery"));
+        }
+
         let total_cost = lottery.ticket_price * data.tickets as u64;
         lottery.prize_pool += total_cost;
         
@@ -103,64 +274,402 @@ async fn buy_ticket(
                 tickets_bought: data.tickets,
             });
         }
-        
+
+        if let Err(e) = store.upsert(lottery) {
+            eprintln!("failed to persist lottery {}: {}", lottery.id, e);
+        }
+
         Ok(HttpResponse::Ok().json(lottery.clone()))
     } else {
         Ok(HttpResponse::NotFound().json("Lottery not found"))
     }
 }
 
+/// True once `end_time` (an RFC 3339 timestamp) is in the past. An unparseable
+/// timestamp is treated as not-yet-due rather than rejecting every request.
+fn end_time_passed(end_time: &str) -> bool {
+    match chrono::DateTime::parse_from_rfc3339(end_time) {
+        Ok(parsed) => parsed.with_timezone(&chrono::Utc) <= chrono::Utc::now(),
+        Err(_) => false,
+    }
+}
+
+fn sha256_hex(input: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(input);
+    hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Sorted `wallet:tickets` digest folded into the draw so the full participant
+/// list, not just the seed, determines the outcome.
+fn participant_digest(participants: &[Participant]) -> String {
+    let mut sorted: Vec<&Participant> = participants.iter().collect();
+    sorted.sort_by(|a, b| a.wallet_address.cmp(&b.wallet_address));
+    sorted
+        .iter()
+        .map(|p| format!("{}:{}", p.wallet_address, p.tickets_bought))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// The commit-reveal inputs (`seed`, `lottery_id`, `digest`) that every
+/// deterministic roll hashes over, bundled so draw functions taking several
+/// other parameters don't need three more on top.
+struct DrawSeed<'a> {
+    seed: &'a str,
+    lottery_id: &'a str,
+    digest: &'a str,
+}
+
+/// Commit-reveal roll over the seed, lottery id and participant digest,
+/// namespaced with a `label` so independent rolls (one per winner rank and
+/// per prize slot) stay reproducible and don't collide with each other.
+fn seeded_roll(draw_seed: &DrawSeed, label: &str, modulus: u64) -> u64 {
+    let mut hasher = Sha256::new();
+    hasher.update(draw_seed.seed.as_bytes());
+    hasher.update(draw_seed.lottery_id.as_bytes());
+    hasher.update(draw_seed.digest.as_bytes());
+    hasher.update(label.as_bytes());
+    let hash = hasher.finalize();
+    let mut be_bytes = [0u8; 8];
+    be_bytes.copy_from_slice(&hash[0..8]);
+    u64::from_be_bytes(be_bytes) % modulus
+}
+
+/// Walks the cumulative ticket ranges to find which participant holds `ticket_number`.
+fn wallet_for_ticket(participants: &[Participant], ticket_number: u32) -> Option<String> {
+    let mut current = 0;
+    for participant in participants {
+        current += participant.tickets_bought;
+        if ticket_number <= current {
+            return Some(participant.wallet_address.clone());
+        }
+    }
+    None
+}
+
+/// Draws up to `winners_count` distinct wallets weighted by tickets, without
+/// replacement: each pick shrinks the candidate pool before the next roll.
+/// Amounts are split equally unless `payout_schedule` gives per-rank basis points.
+fn draw_winners_without_replacement(
+    participants: &[Participant],
+    winners_count: u32,
+    payout_schedule: &Option<Vec<u64>>,
+    prize_pool: u64,
+    seed: &str,
+    lottery_id: &str,
+    digest: &str,
+) -> Vec<WinnerEntry> {
+    let draw_seed = DrawSeed { seed, lottery_id, digest };
+    let mut candidates: Vec<Participant> = participants.to_vec();
+    let mut winners = Vec::new();
+
+    for rank in 1..=winners_count {
+        let total_tickets: u32 = candidates.iter().map(|p| p.tickets_bought).sum();
+        if total_tickets == 0 {
+            break;
+        }
+        let ticket_roll =
+            seeded_roll(&draw_seed, &format!("rank:{}", rank), total_tickets as u64) as u32 + 1;
+
+        let mut current = 0;
+        let picked = candidates.iter().position(|p| {
+            current += p.tickets_bought;
+            ticket_roll <= current
+        });
+        match picked {
+            Some(idx) => {
+                let wallet = candidates.remove(idx).wallet_address;
+                winners.push(WinnerEntry { wallet, rank, amount: 0 });
+            }
+            None => break,
+        }
+    }
+
+    match payout_schedule {
+        Some(schedule) => {
+            for winner in winners.iter_mut() {
+                let bps = schedule.get((winner.rank - 1) as usize).copied().unwrap_or(0);
+                winner.amount = prize_pool * bps / 10000;
+            }
+        }
+        None if !winners.is_empty() => {
+            let share = prize_pool / winners.len() as u64;
+            for winner in winners.iter_mut() {
+                winner.amount = share;
+            }
+        }
+        None => {}
+    }
+
+    winners
+}
+
+/// Splits a reserved "pity pool" among participants who didn't win a draw
+/// slot and didn't receive a tiered award, either proportional to the
+/// tickets they bought or as a flat amount per loser.
+fn compute_consolation(
+    participants: &[Participant],
+    winners: &[WinnerEntry],
+    awards: &[Award],
+    consolation_bps: u64,
+    refund_policy: RefundPolicy,
+    prize_pool: u64,
+) -> Vec<ConsolationEntry> {
+    if refund_policy == RefundPolicy::None || consolation_bps == 0 {
+        return Vec::new();
+    }
+
+    let paid_wallets: std::collections::HashSet<&str> = winners
+        .iter()
+        .map(|w| w.wallet.as_str())
+        .chain(awards.iter().map(|a| a.wallet.as_str()))
+        .collect();
+    let losers: Vec<&Participant> = participants
+        .iter()
+        .filter(|p| !paid_wallets.contains(p.wallet_address.as_str()))
+        .collect();
+    if losers.is_empty() {
+        return Vec::new();
+    }
+
+    let reserved = prize_pool * consolation_bps / 10000;
+    match refund_policy {
+        RefundPolicy::Proportional => {
+            let total_loser_tickets: u64 = losers.iter().map(|p| p.tickets_bought as u64).sum();
+            if total_loser_tickets == 0 {
+                return Vec::new();
+            }
+            losers
+                .iter()
+                .map(|p| ConsolationEntry {
+                    wallet: p.wallet_address.clone(),
+                    amount: reserved * p.tickets_bought as u64 / total_loser_tickets,
+                })
+                .collect()
+        }
+        RefundPolicy::Flat => {
+            let share = reserved / losers.len() as u64;
+            losers
+                .iter()
+                .map(|p| ConsolationEntry {
+                    wallet: p.wallet_address.clone(),
+                    amount: share,
+                })
+                .collect()
+        }
+        RefundPolicy::None => Vec::new(),
+    }
+}
+
+/// Rolls one award per prize slot: ensured tiers claim a slot deterministically,
+/// the remaining slots sample a tier weighted by `ratio` among non-ensured tiers.
+fn draw_tiered_awards(
+    tiers: &[PrizeTier],
+    slots: u32,
+    prize_pool: u64,
+    participants: &[Participant],
+    total_tickets: u32,
+    draw_seed: &DrawSeed,
+) -> Vec<Award> {
+    let mut ensured = tiers.iter().filter(|t| t.ensured);
+    let non_ensured: Vec<&PrizeTier> = tiers.iter().filter(|t| !t.ensured).collect();
+    let non_ensured_ratio_total: u64 = non_ensured.iter().map(|t| t.ratio).sum();
+
+    // Each slot pays out against what the previous slots left behind, not
+    // the original `prize_pool` snapshot, so two ensured 100%-bps tiers
+    // can't each separately claim the whole pool.
+    let mut remaining_pool = prize_pool;
+    let mut awards = Vec::new();
+    for slot in 0..slots {
+        let tier = match ensured.next() {
+            Some(t) => t,
+            None if !non_ensured.is_empty() && non_ensured_ratio_total > 0 => {
+                let roll = seeded_roll(draw_seed, &format!("tier:{}", slot), non_ensured_ratio_total);
+                let mut acc = 0u64;
+                non_ensured
+                    .iter()
+                    .find(|t| {
+                        acc += t.ratio;
+                        roll < acc
+                    })
+                    .copied()
+                    .unwrap_or_else(|| non_ensured[non_ensured.len() - 1])
+            }
+            None => continue,
+        };
+
+        let ticket_roll =
+            seeded_roll(draw_seed, &format!("tier-wallet:{}", slot), total_tickets as u64) as u32 + 1;
+        if let Some(wallet) = wallet_for_ticket(participants, ticket_roll) {
+            let amount = remaining_pool * tier.payout_bps / 10000;
+            remaining_pool = remaining_pool.saturating_sub(amount);
+            awards.push(Award { tier: tier.name.clone(), wallet, amount });
+        }
+    }
+    awards
+}
+
+/// Runs the full draw against an already-verified seed: winners, consolation
+/// refunds and tiered awards, then marks the lottery ended. Shared by the
+/// admin-triggered `pick_winner` handler and the auto-draw sweeper.
+fn run_draw(lottery: &mut Lottery, seed: &str) {
+    let total_tickets: u32 = lottery.participants.iter().map(|p| p.tickets_bought).sum();
+    let digest = participant_digest(&lottery.participants);
+
+    // Winners, tiered awards and consolation refunds share one `prize_pool` —
+    // each is computed against what the previous source left behind, so the
+    // three payout sources can never add up to more than was collected.
+    let mut remaining_pool = lottery.prize_pool;
+
+    lottery.winners = draw_winners_without_replacement(
+        &lottery.participants,
+        lottery.winners_count,
+        &lottery.payout_schedule,
+        remaining_pool,
+        seed,
+        &lottery.id,
+        &digest,
+    );
+    lottery.winner = lottery.winners.first().map(|w| w.wallet.clone());
+    let winners_total: u64 = lottery.winners.iter().map(|w| w.amount).sum();
+    remaining_pool = remaining_pool.saturating_sub(winners_total);
+
+    let draw_seed = DrawSeed { seed, lottery_id: &lottery.id, digest: &digest };
+    lottery.awards = draw_tiered_awards(
+        &lottery.prize_tiers,
+        lottery.prize_slots,
+        remaining_pool,
+        &lottery.participants,
+        total_tickets,
+        &draw_seed,
+    );
+    let awards_total: u64 = lottery.awards.iter().map(|a| a.amount).sum();
+    remaining_pool = remaining_pool.saturating_sub(awards_total);
+
+    lottery.consolation = compute_consolation(
+        &lottery.participants,
+        &lottery.winners,
+        &lottery.awards,
+        lottery.consolation_bps,
+        lottery.refund_policy,
+        remaining_pool,
+    );
+
+    lottery.revealed_seed = Some(seed.to_string());
+    lottery.participant_digest = Some(digest);
+    lottery.is_active = false;
+}
+
 async fn pick_winner(
     lottery_id: web::Path<String>,
     admin_data: web::Json<PickWinnerRequest>,
     state: web::Data<LotteryState>,
+    store: web::Data<LotteryStoreHandle>,
 ) -> Result<HttpResponse> {
     let mut lotteries = state.lock().unwrap();
-    
+
     if let Some(lottery) = lotteries.get_mut(lottery_id.as_str()) {
         // Check admin authorization
         if lottery.admin != admin_data.admin {
             return Ok(HttpResponse::Forbidden().json("Only the lottery admin can pick a winner"));
         }
-        
+
         if !lottery.is_active {
             return Ok(HttpResponse::BadRequest().json("Lottery is already ended"));
         }
-        
+
         if lottery.participants.is_empty() {
             return Ok(HttpResponse::BadRequest().json("No participants in lottery"));
         }
-        
+
         // Calculate total tickets across all participants
         let total_tickets: u32 = lottery.participants.iter().map(|p| p.tickets_bought).sum();
         if total_tickets == 0 {
             return Ok(HttpResponse::BadRequest().json("No tickets sold"));
         }
-        
-        // Use cryptographically secure random selection
-        use rand::rngs::OsRng;
-        use rand::RngCore;
-        let mut rng = OsRng;
-        let winning_ticket_number = (rng.next_u32() % total_tickets) + 1;
-        
-        // Find the winner without creating a large vector
-        let mut current_ticket = 0;
-        for participant in &lottery.participants {
-            current_ticket += participant.tickets_bought;
-            if winning_ticket_number <= current_ticket {
-                lottery.winner = Some(participant.wallet_address.clone());
-                break;
-            }
+
+        // Verify the revealed seed matches the commitment made at creation time.
+        if sha256_hex(admin_data.seed.as_bytes()) != lottery.seed_commitment {
+            return Ok(HttpResponse::BadRequest().json("Seed does not match seed_commitment"));
         }
-        
-        lottery.is_active = false;
-        
+
+        run_draw(lottery, &admin_data.seed);
+
+        if let Err(e) = store.upsert(lottery) {
+            eprintln!("failed to persist lottery {}: {}", lottery.id, e);
+        }
+
         Ok(HttpResponse::Ok().json(lottery.clone()))
     } else {
         Ok(HttpResponse::NotFound().json("Lottery not found"))
     }
 }
 
+async fn verify_lottery(
+    lottery_id: web::Path<String>,
+    state: web::Data<LotteryState>,
+) -> Result<HttpResponse> {
+    let lotteries = state.lock().unwrap();
+
+    if let Some(lottery) = lotteries.get(lottery_id.as_str()) {
+        let total_tickets: u32 = lottery.participants.iter().map(|p| p.tickets_bought).sum();
+        let mut remaining_pool = lottery.prize_pool;
+
+        let winners = match (&lottery.revealed_seed, &lottery.participant_digest) {
+            (Some(seed), Some(digest)) if total_tickets > 0 => draw_winners_without_replacement(
+                &lottery.participants,
+                lottery.winners_count,
+                &lottery.payout_schedule,
+                remaining_pool,
+                seed,
+                &lottery.id,
+                digest,
+            ),
+            _ => Vec::new(),
+        };
+        let winners_total: u64 = winners.iter().map(|w| w.amount).sum();
+        remaining_pool = remaining_pool.saturating_sub(winners_total);
+
+        let awards = match (&lottery.revealed_seed, &lottery.participant_digest) {
+            (Some(seed), Some(digest)) if total_tickets > 0 => draw_tiered_awards(
+                &lottery.prize_tiers,
+                lottery.prize_slots,
+                remaining_pool,
+                &lottery.participants,
+                total_tickets,
+                &DrawSeed { seed, lottery_id: &lottery.id, digest },
+            ),
+            _ => Vec::new(),
+        };
+        let awards_total: u64 = awards.iter().map(|a| a.amount).sum();
+        remaining_pool = remaining_pool.saturating_sub(awards_total);
+
+        let consolation = compute_consolation(
+            &lottery.participants,
+            &winners,
+            &awards,
+            lottery.consolation_bps,
+            lottery.refund_policy,
+            remaining_pool,
+        );
+
+        Ok(HttpResponse::Ok().json(VerifyResponse {
+            lottery_id: lottery.id.clone(),
+            seed_commitment: lottery.seed_commitment.clone(),
+            revealed_seed: lottery.revealed_seed.clone(),
+            participant_digest: lottery.participant_digest.clone(),
+            total_tickets,
+            winners,
+            winner: lottery.winner.clone(),
+            consolation,
+            awards,
+        }))
+    } else {
+        Ok(HttpResponse::NotFound().json("Lottery not found"))
+    }
+}
+
 async fn get_lottery(
     lottery_id: web::Path<String>,
     state: web::Data<LotteryState>,
@@ -184,22 +693,61 @@ async fn health_check() -> Result<HttpResponse> {
     Ok(HttpResponse::Ok().json("Rondo Mundi Backend is running!"))
 }
 
+/// Periodically sweeps for `auto_draw` lotteries whose `end_time` has passed
+/// and runs the draw on their behalf, so admins don't have to call
+/// `pick_winner` manually once sales close.
+fn spawn_auto_draw_sweeper(state: LotteryState, store: LotteryStoreHandle) {
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(std::time::Duration::from_secs(30));
+        loop {
+            interval.tick().await;
+            let mut lotteries = state.lock().unwrap();
+            for lottery in lotteries.values_mut() {
+                if !lottery.is_active || !lottery.auto_draw || lottery.participants.is_empty() {
+                    continue;
+                }
+                let due = lottery.end_time.as_deref().map(end_time_passed).unwrap_or(false);
+                if !due {
+                    continue;
+                }
+                let seed = match lottery.auto_draw_seed.clone() {
+                    Some(seed) => seed,
+                    None => continue,
+                };
+
+                run_draw(lottery, &seed);
+                if let Err(e) = store.upsert(lottery) {
+                    eprintln!("failed to persist auto-drawn lottery {}: {}", lottery.id, e);
+                }
+            }
+        }
+    });
+}
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     env_logger::init();
-    
-    let lottery_state: LotteryState = Arc::new(Mutex::new(HashMap::new()));
-    
+
+    let lottery_store: LotteryStoreHandle = Arc::new(
+        FileLotteryStore::new("./data/lotteries").expect("failed to initialize lottery store"),
+    );
+    let loaded = lottery_store.load_all().expect("failed to load lotteries from disk");
+    println!("Loaded {} lotteries from disk", loaded.len());
+    let lottery_state: LotteryState = Arc::new(Mutex::new(loaded));
+
+    spawn_auto_draw_sweeper(lottery_state.clone(), lottery_store.clone());
+
     println!("ðŸŽ² Starting Rondo Mundi backend server on 0.0.0.0:8080");
-    
+
     HttpServer::new(move || {
         let cors = Cors::default()
             .allow_any_origin()
             .allow_any_method()
             .allow_any_header();
-            
+
         App::new()
             .app_data(web::Data::new(lottery_state.clone()))
+            .app_data(web::Data::new(lottery_store.clone()))
             .wrap(cors)
             .wrap(Logger::default())
             .route("/", web::get().to(health_check))
@@ -208,9 +756,267 @@ async fn main() -> std::io::Result<()> {
             .route("/lottery/{lottery_id}", web::get().to(get_lottery))
             .route("/lottery/{lottery_id}/buy", web::post().to(buy_ticket))
             .route("/lottery/{lottery_id}/pick_winner", web::post().to(pick_winner))
+            .route("/lottery/{lottery_id}/verify", web::get().to(verify_lottery))
             .route("/lotteries", web::get().to(get_all_lotteries))
     })
     .bind("0.0.0.0:8080")?
     .run()
     .await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn participant(wallet: &str, tickets: u32) -> Participant {
+        Participant { wallet_address: wallet.to_string(), tickets_bought: tickets }
+    }
+
+    #[test]
+    fn draw_winners_without_replacement_never_repeats_a_wallet() {
+        let participants = vec![
+            participant("alice", 10),
+            participant("bob", 10),
+            participant("carol", 10),
+            participant("dave", 10),
+        ];
+
+        let winners = draw_winners_without_replacement(
+            &participants,
+            3,
+            &None,
+            900,
+            "revealed-seed",
+            "lottery-1",
+            "digest-1",
+        );
+
+        assert_eq!(winners.len(), 3);
+        let wallets: std::collections::HashSet<&str> =
+            winners.iter().map(|w| w.wallet.as_str()).collect();
+        assert_eq!(wallets.len(), 3, "each winner must be a distinct wallet");
+    }
+
+    #[test]
+    fn draw_winners_without_replacement_splits_pool_equally_with_no_schedule() {
+        let participants = vec![participant("alice", 5), participant("bob", 5)];
+
+        let winners = draw_winners_without_replacement(
+            &participants,
+            2,
+            &None,
+            900,
+            "revealed-seed",
+            "lottery-2",
+            "digest-2",
+        );
+
+        let total: u64 = winners.iter().map(|w| w.amount).sum();
+        assert_eq!(winners.len(), 2);
+        assert_eq!(total, 900);
+    }
+
+    #[test]
+    fn draw_winners_without_replacement_honours_payout_schedule() {
+        let participants = vec![participant("alice", 5), participant("bob", 5)];
+
+        let winners = draw_winners_without_replacement(
+            &participants,
+            2,
+            &Some(vec![7000, 3000]),
+            1000,
+            "revealed-seed",
+            "lottery-3",
+            "digest-3",
+        );
+
+        let total: u64 = winners.iter().map(|w| w.amount).sum();
+        assert_eq!(total, 1000);
+        assert_eq!(winners.iter().find(|w| w.rank == 1).unwrap().amount, 700);
+        assert_eq!(winners.iter().find(|w| w.rank == 2).unwrap().amount, 300);
+    }
+
+    #[test]
+    fn draw_winners_without_replacement_is_deterministic_for_a_fixed_seed() {
+        let participants = vec![
+            participant("alice", 10),
+            participant("bob", 10),
+            participant("carol", 10),
+        ];
+
+        let first = draw_winners_without_replacement(
+            &participants,
+            2,
+            &None,
+            600,
+            "same-seed",
+            "lottery-4",
+            "digest-4",
+        );
+        let second = draw_winners_without_replacement(
+            &participants,
+            2,
+            &None,
+            600,
+            "same-seed",
+            "lottery-4",
+            "digest-4",
+        );
+
+        let first_wallets: Vec<&str> = first.iter().map(|w| w.wallet.as_str()).collect();
+        let second_wallets: Vec<&str> = second.iter().map(|w| w.wallet.as_str()).collect();
+        assert_eq!(first_wallets, second_wallets);
+    }
+
+    #[test]
+    fn compute_consolation_never_exceeds_the_pool_it_was_given() {
+        let participants =
+            vec![participant("alice", 5), participant("bob", 5), participant("carol", 5)];
+        let winners = vec![WinnerEntry { wallet: "alice".to_string(), rank: 1, amount: 1000 }];
+
+        // Mirrors the cascading-budget fix: callers now pass the *remaining*
+        // pool (after winners/awards are already committed), not the full
+        // prize_pool, so consolation can never overrun what's left.
+        let remaining_pool = 200;
+        let consolation = compute_consolation(
+            &participants,
+            &winners,
+            &[],
+            3000,
+            RefundPolicy::Proportional,
+            remaining_pool,
+        );
+
+        let total: u64 = consolation.iter().map(|c| c.amount).sum();
+        assert!(total <= remaining_pool, "consolation paid out more than it was reserved");
+    }
+
+    #[test]
+    fn compute_consolation_splits_flat_refunds_evenly_among_losers() {
+        let participants =
+            vec![participant("alice", 1), participant("bob", 1), participant("carol", 1)];
+        let winners = vec![WinnerEntry { wallet: "alice".to_string(), rank: 1, amount: 1000 }];
+
+        let consolation =
+            compute_consolation(&participants, &winners, &[], 10000, RefundPolicy::Flat, 200);
+
+        assert_eq!(consolation.len(), 2);
+        assert!(consolation.iter().all(|c| c.amount == 100));
+    }
+
+    #[test]
+    fn compute_consolation_is_empty_when_refund_policy_is_none() {
+        let participants = vec![participant("alice", 1), participant("bob", 1)];
+        let winners = vec![WinnerEntry { wallet: "alice".to_string(), rank: 1, amount: 1000 }];
+
+        let consolation =
+            compute_consolation(&participants, &winners, &[], 5000, RefundPolicy::None, 200);
+
+        assert!(consolation.is_empty());
+    }
+
+    #[test]
+    fn compute_consolation_excludes_wallets_that_already_received_a_tiered_award() {
+        let participants =
+            vec![participant("alice", 1), participant("bob", 1), participant("carol", 1)];
+        let winners = vec![WinnerEntry { wallet: "alice".to_string(), rank: 1, amount: 1000 }];
+        let awards = vec![Award { tier: "minor".to_string(), wallet: "bob".to_string(), amount: 100 }];
+
+        let consolation =
+            compute_consolation(&participants, &winners, &awards, 5000, RefundPolicy::Flat, 200);
+
+        assert_eq!(consolation.len(), 1);
+        assert_eq!(consolation[0].wallet, "carol");
+    }
+
+    #[test]
+    fn sha256_hex_matches_a_known_digest() {
+        // Empty-input SHA-256 is a standard test vector.
+        assert_eq!(
+            sha256_hex(b""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn sha256_hex_round_trips_through_commit_reveal() {
+        let seed = "super-secret-seed";
+        let commitment = sha256_hex(seed.as_bytes());
+        assert_eq!(sha256_hex(seed.as_bytes()), commitment);
+        assert_ne!(sha256_hex(b"wrong-seed"), commitment);
+    }
+
+    #[test]
+    fn participant_digest_is_order_independent() {
+        let a = vec![participant("alice", 3), participant("bob", 5)];
+        let b = vec![participant("bob", 5), participant("alice", 3)];
+        assert_eq!(participant_digest(&a), participant_digest(&b));
+    }
+
+    #[test]
+    fn participant_digest_changes_when_tickets_change() {
+        let a = vec![participant("alice", 3)];
+        let b = vec![participant("alice", 4)];
+        assert_ne!(participant_digest(&a), participant_digest(&b));
+    }
+
+    fn prize_tier(name: &str, ratio: u64, payout_bps: u64, ensured: bool) -> PrizeTier {
+        PrizeTier { name: name.to_string(), ratio, payout_bps, ensured }
+    }
+
+    #[test]
+    fn draw_tiered_awards_claims_one_slot_per_ensured_tier() {
+        let participants = vec![participant("alice", 5), participant("bob", 5)];
+        let tiers = vec![prize_tier("jackpot", 0, 10000, true)];
+        let draw_seed = DrawSeed { seed: "seed", lottery_id: "lottery-5", digest: "digest-5" };
+
+        let awards = draw_tiered_awards(&tiers, 1, 1000, &participants, 10, &draw_seed);
+
+        assert_eq!(awards.len(), 1);
+        assert_eq!(awards[0].tier, "jackpot");
+    }
+
+    #[test]
+    fn draw_tiered_awards_never_exceeds_the_pool_it_was_given() {
+        // Two ensured tiers that would each separately claim the whole pool
+        // at 100% payout_bps must still be capped by what's left after the
+        // first slot is paid.
+        let participants = vec![participant("alice", 5), participant("bob", 5)];
+        let tiers =
+            vec![prize_tier("jackpot", 0, 10000, true), prize_tier("runner-up", 0, 10000, true)];
+        let draw_seed = DrawSeed { seed: "seed", lottery_id: "lottery-6", digest: "digest-6" };
+
+        let awards = draw_tiered_awards(&tiers, 2, 1000, &participants, 10, &draw_seed);
+
+        let total: u64 = awards.iter().map(|a| a.amount).sum();
+        assert!(total <= 1000, "tiered awards paid out more than the pool held");
+    }
+
+    #[test]
+    fn draw_tiered_awards_samples_non_ensured_tiers_by_ratio() {
+        let participants = vec![participant("alice", 5), participant("bob", 5)];
+        let tiers = vec![prize_tier("common", 1, 1000, false), prize_tier("rare", 3, 2000, false)];
+        let draw_seed = DrawSeed { seed: "seed", lottery_id: "lottery-7", digest: "digest-7" };
+
+        let awards = draw_tiered_awards(&tiers, 1, 1000, &participants, 10, &draw_seed);
+
+        assert_eq!(awards.len(), 1);
+        assert!(tiers.iter().any(|t| t.name == awards[0].tier));
+    }
+
+    #[test]
+    fn end_time_passed_is_true_once_the_deadline_is_behind_us() {
+        assert!(end_time_passed("2000-01-01T00:00:00Z"));
+    }
+
+    #[test]
+    fn end_time_passed_is_false_while_the_deadline_is_ahead_of_us() {
+        assert!(!end_time_passed("2999-01-01T00:00:00Z"));
+    }
+
+    #[test]
+    fn end_time_passed_is_false_for_an_unparseable_timestamp() {
+        // The sweeper must never auto-draw a lottery it can't confirm is due.
+        assert!(!end_time_passed("not-a-timestamp"));
+    }
+}
@@ -0,0 +1,161 @@
+use crate::Lottery;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// Abstraction over where lotteries are persisted, so handlers never depend on
+/// a concrete storage backend directly. `FileLotteryStore` is the only
+/// implementation today; a SQL-backed one can be dropped in later behind the
+/// same trait.
+pub trait LotteryStore: Send + Sync {
+    fn load_all(&self) -> io::Result<HashMap<String, Lottery>>;
+    fn upsert(&self, lottery: &Lottery) -> io::Result<()>;
+}
+
+/// Persists each lottery as its own JSON file, one per `{lottery_id}.json`.
+/// Writes go through a temp file + rename so a crash mid-write can never
+/// leave a half-written lottery on disk.
+pub struct FileLotteryStore {
+    dir: PathBuf,
+}
+
+impl FileLotteryStore {
+    pub fn new(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    fn path_for(&self, lottery_id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", lottery_id))
+    }
+
+    fn tmp_path_for(&self, lottery_id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json.tmp", lottery_id))
+    }
+}
+
+fn to_io_error(err: serde_json::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+impl LotteryStore for FileLotteryStore {
+    fn load_all(&self) -> io::Result<HashMap<String, Lottery>> {
+        let mut lotteries = HashMap::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let contents = match fs::read_to_string(&path) {
+                Ok(contents) => contents,
+                Err(err) => {
+                    log::warn!("skipping unreadable lottery file {}: {}", path.display(), err);
+                    continue;
+                }
+            };
+            let lottery: Lottery = match serde_json::from_str(&contents) {
+                Ok(lottery) => lottery,
+                Err(err) => {
+                    log::warn!("skipping corrupted lottery file {}: {}", path.display(), err);
+                    continue;
+                }
+            };
+            lotteries.insert(lottery.id.clone(), lottery);
+        }
+        Ok(lotteries)
+    }
+
+    fn upsert(&self, lottery: &Lottery) -> io::Result<()> {
+        let tmp_path = self.tmp_path_for(&lottery.id);
+        let contents = serde_json::to_string_pretty(lottery).map_err(to_io_error)?;
+        fs::write(&tmp_path, contents)?;
+        fs::rename(&tmp_path, self.path_for(&lottery.id))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Participant, PrizeTier, RefundPolicy};
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    static TEST_DIR_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+    fn test_store() -> (FileLotteryStore, PathBuf) {
+        let dir = std::env::temp_dir().join(format!(
+            "rondo-mundi-store-test-{}-{}",
+            std::process::id(),
+            TEST_DIR_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        (FileLotteryStore::new(&dir).expect("failed to create test store"), dir)
+    }
+
+    fn sample_lottery(id: &str) -> Lottery {
+        Lottery {
+            id: id.to_string(),
+            admin: "admin".to_string(),
+            ticket_price: 100,
+            participants: vec![Participant { wallet_address: "alice".to_string(), tickets_bought: 1 }],
+            is_active: true,
+            prize_pool: 100,
+            winner: None,
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            end_time: None,
+            seed_commitment: "commitment".to_string(),
+            revealed_seed: None,
+            participant_digest: None,
+            prize_tiers: Vec::<PrizeTier>::new(),
+            prize_slots: 0,
+            awards: Vec::new(),
+            winners_count: 1,
+            payout_schedule: None,
+            winners: Vec::new(),
+            consolation_bps: 0,
+            refund_policy: RefundPolicy::None,
+            consolation: Vec::new(),
+            auto_draw: false,
+            auto_draw_seed: None,
+        }
+    }
+
+    #[test]
+    fn upsert_then_load_all_round_trips_a_lottery() {
+        let (store, dir) = test_store();
+        let lottery = sample_lottery("lottery-a");
+
+        store.upsert(&lottery).expect("upsert should succeed");
+        let loaded = store.load_all().expect("load_all should succeed");
+
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded.get("lottery-a").unwrap().admin, "admin");
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn upsert_leaves_no_leftover_tmp_file() {
+        let (store, dir) = test_store();
+        let lottery = sample_lottery("lottery-b");
+
+        store.upsert(&lottery).expect("upsert should succeed");
+
+        assert!(!store.tmp_path_for("lottery-b").exists());
+        assert!(store.path_for("lottery-b").exists());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn load_all_skips_a_corrupted_file_and_still_loads_the_rest() {
+        let (store, dir) = test_store();
+        store.upsert(&sample_lottery("lottery-c")).expect("upsert should succeed");
+        fs::write(dir.join("corrupted.json"), "not valid json").expect("write should succeed");
+
+        let loaded = store.load_all().expect("load_all should recover from the bad file");
+
+        assert_eq!(loaded.len(), 1);
+        assert!(loaded.contains_key("lottery-c"));
+        fs::remove_dir_all(&dir).ok();
+    }
+}